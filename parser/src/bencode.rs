@@ -0,0 +1,119 @@
+//! Minimal bencode scanner used to locate the raw byte slice of a top-level
+//! dictionary value, without re-serializing it.
+//!
+//! This exists because hashes like the torrent `info_hash` must be computed
+//! over the *exact* bytes a key occupied in the original file; round-tripping
+//! through serde can reorder keys, drop unknown fields, or normalize integers
+//! and would silently produce the wrong hash.
+
+/// Returns the raw byte slice of the value associated with `key` in the
+/// top-level dictionary of `data`, or `None` if `data` isn't a bencoded
+/// dictionary or doesn't contain `key`.
+pub(crate) fn locate_top_level_key<'a>(data: &'a [u8], key: &[u8]) -> Option<&'a [u8]> {
+    if *data.first()? != b'd' {
+        return None;
+    }
+
+    let mut pos = 1;
+    loop {
+        if *data.get(pos)? == b'e' {
+            return None;
+        }
+
+        let (key_len, key_start) = read_string_header(data, pos)?;
+        let value_start = key_start + key_len;
+        if &data[key_start..value_start] == key {
+            let value_end = skip_value(data, value_start)?;
+            return Some(&data[value_start..value_end]);
+        }
+
+        pos = skip_value(data, value_start)?;
+    }
+}
+
+/// Parses a bencode string header (`<len>:`) starting at `pos`, returning the
+/// string's length and the position right after the `:`.
+fn read_string_header(data: &[u8], pos: usize) -> Option<(usize, usize)> {
+    let mut end = pos;
+    while *data.get(end)? != b':' {
+        end += 1;
+    }
+    let len = std::str::from_utf8(&data[pos..end]).ok()?.parse().ok()?;
+    Some((len, end + 1))
+}
+
+/// Advances past a single bencode value (integer, string, list, or
+/// dictionary) starting at `pos`, tracking `d`/`l` ... `e` nesting so that
+/// nested containers don't get mistaken for the end of the outer one, and
+/// returns the position right after it.
+fn skip_value(data: &[u8], pos: usize) -> Option<usize> {
+    match *data.get(pos)? {
+        b'i' => {
+            let mut end = pos + 1;
+            while *data.get(end)? != b'e' {
+                end += 1;
+            }
+            Some(end + 1)
+        }
+        b'l' | b'd' => {
+            let mut cursor = pos + 1;
+            while *data.get(cursor)? != b'e' {
+                cursor = skip_value(data, cursor)?;
+            }
+            Some(cursor + 1)
+        }
+        b'0'..=b'9' => {
+            let (len, value_start) = read_string_header(data, pos)?;
+            let value_end = value_start.checked_add(len)?;
+            (value_end <= data.len()).then_some(value_end)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locates_a_simple_string_value() {
+        let data = b"d4:name5:helloe";
+        assert_eq!(locate_top_level_key(data, b"name"), Some(&b"5:hello"[..]));
+    }
+
+    #[test]
+    fn locates_a_value_past_nested_dicts_and_lists() {
+        let data = b"d4:infod4:name3:foo5:filesl4:spami42eee8:announce3:fooe";
+        assert_eq!(
+            locate_top_level_key(data, b"info"),
+            Some(&b"d4:name3:foo5:filesl4:spami42eee"[..])
+        );
+        assert_eq!(locate_top_level_key(data, b"announce"), Some(&b"3:foo"[..]));
+    }
+
+    #[test]
+    fn returns_none_for_a_missing_key() {
+        let data = b"d4:name5:helloe";
+        assert_eq!(locate_top_level_key(data, b"missing"), None);
+    }
+
+    #[test]
+    fn returns_none_for_a_non_dict_value() {
+        let data = b"5:hello";
+        assert_eq!(locate_top_level_key(data, b"name"), None);
+    }
+
+    #[test]
+    fn returns_none_for_truncated_input() {
+        // The "name" value claims to be 5 bytes long, but only 2 remain.
+        let data = b"d4:name5:he";
+        assert_eq!(locate_top_level_key(data, b"name"), None);
+    }
+
+    #[test]
+    fn finds_keys_in_an_unclosed_dict_but_returns_none_once_input_runs_out() {
+        let data = b"d4:name5:hello";
+        assert_eq!(locate_top_level_key(data, b"name"), Some(&b"5:hello"[..]));
+        assert_eq!(locate_top_level_key(data, b"missing"), None);
+    }
+}
@@ -0,0 +1,319 @@
+//! Constructs `.torrent` files from on-disk content — the write-side
+//! counterpart to parsing.
+
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use sha1::{Digest, Sha1};
+
+use crate::file::v1::{TorrentFileEntry, TorrentInfo as TorrentInfoV1, TorrentInfoContent};
+use crate::file::{TorrentInfo, TorrentInfoBase};
+use crate::TorrentFile;
+
+/// Errors that can occur while building a torrent from on-disk content.
+#[derive(Debug)]
+pub enum BuilderError {
+    /// An I/O error occurred while walking or reading the source.
+    Io(std::io::Error),
+    /// The constructed `TorrentFile` could not be bencoded while computing
+    /// its `info_hash`.
+    Encode(serde_bencode::Error),
+    /// The requested piece length was `0`, which can't divide any content
+    /// into pieces.
+    InvalidPieceLength,
+}
+
+impl From<std::io::Error> for BuilderError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<serde_bencode::Error> for BuilderError {
+    fn from(value: serde_bencode::Error) -> Self {
+        Self::Encode(value)
+    }
+}
+
+impl std::fmt::Display for BuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(inner) => write!(f, "failed to read source content: {inner}"),
+            Self::Encode(inner) => write!(f, "failed to bencode the built torrent: {inner}"),
+            Self::InvalidPieceLength => write!(f, "piece length must not be 0"),
+        }
+    }
+}
+
+impl std::error::Error for BuilderError {}
+
+/// Picks a sane default piece length (a power-of-two byte count) for
+/// `total_len` bytes of content, aiming to keep the piece count (and so the
+/// size of `pieces`) within a reasonable range regardless of torrent size.
+pub fn pick_piece_length(total_len: u64) -> u64 {
+    const MIN: u64 = 16 * 1024;
+    const MAX: u64 = 16 * 1024 * 1024;
+    const TARGET_PIECE_COUNT: u64 = 1500;
+
+    let mut piece_length = MIN;
+    while piece_length < MAX && total_len / piece_length > TARGET_PIECE_COUNT {
+        piece_length *= 2;
+    }
+    piece_length
+}
+
+/// Builds a v1 `.torrent` file (BEP 3) from a source file or directory on
+/// disk.
+#[derive(Debug, Clone, Default)]
+pub struct TorrentBuilder {
+    piece_length: Option<u64>,
+    announce: Option<String>,
+    announce_list: Vec<Vec<String>>,
+    comment: Option<String>,
+    created_by: Option<String>,
+}
+
+impl TorrentBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Overrides the piece length to use, instead of the one picked by
+    /// [`pick_piece_length`].
+    pub fn piece_length(mut self, piece_length: u64) -> Self {
+        self.piece_length = Some(piece_length);
+        self
+    }
+
+    pub fn announce(mut self, announce: impl Into<String>) -> Self {
+        self.announce = Some(announce.into());
+        self
+    }
+
+    pub fn announce_list(mut self, announce_list: Vec<Vec<String>>) -> Self {
+        self.announce_list = announce_list;
+        self
+    }
+
+    pub fn comment(mut self, comment: impl Into<String>) -> Self {
+        self.comment = Some(comment.into());
+        self
+    }
+
+    pub fn created_by(mut self, created_by: impl Into<String>) -> Self {
+        self.created_by = Some(created_by.into());
+        self
+    }
+
+    /// Reads `source` (a single file or a directory tree) and builds a
+    /// `TorrentFile` describing its content.
+    pub fn build(self, source: &Path) -> Result<TorrentFile, BuilderError> {
+        let name = source
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let files = collect_files(source)?;
+        let total_len: u64 = files.iter().map(|(_, length)| *length).sum();
+        let piece_length = self
+            .piece_length
+            .unwrap_or_else(|| pick_piece_length(total_len));
+        if piece_length == 0 {
+            return Err(BuilderError::InvalidPieceLength);
+        }
+        let pieces = hash_pieces(&files, piece_length)?;
+
+        let content = if source.is_dir() {
+            TorrentInfoContent::Directory {
+                files: files
+                    .iter()
+                    .map(|(path, length)| TorrentFileEntry {
+                        length: *length,
+                        path: path
+                            .strip_prefix(source)
+                            .unwrap_or(path)
+                            .components()
+                            .map(|part| part.as_os_str().to_string_lossy().into_owned())
+                            .collect(),
+                        md5sum: None,
+                    })
+                    .collect(),
+            }
+        } else {
+            TorrentInfoContent::File {
+                length: total_len,
+                md5sum: None,
+            }
+        };
+
+        let info = TorrentInfoV1 {
+            base: TorrentInfoBase {
+                name,
+                piece_length,
+                private: None,
+                md5sum: None,
+            },
+            fields: crate::file::v1::TorrentInfoFields {
+                pieces: serde_bytes::ByteBuf::from(pieces),
+                content,
+            },
+        };
+
+        let mut file = TorrentFile {
+            announce: self.announce,
+            announce_list: self.announce_list,
+            creation_date: None,
+            comment: self.comment,
+            created_by: self.created_by,
+            encoding: None,
+            info: TorrentInfo::V1(info),
+            piece_layers: Default::default(),
+            raw_info: Vec::new(),
+        };
+
+        // `info_hash_bytes`/`info_hash_v2_bytes` hash `raw_info`, so it must
+        // be populated right away rather than only after a manual
+        // `to_bytes`/`from_bytes` round-trip.
+        let bytes = file.to_bytes()?;
+        file.raw_info = crate::bencode::locate_top_level_key(&bytes, b"info")
+            .expect("a freshly built torrent always bencodes to a dict with an info key")
+            .to_vec();
+
+        Ok(file)
+    }
+}
+
+/// Lists every regular file under `source`, depth-first in path order, as
+/// `(path, length)` pairs, each path starting with `source`. `source` itself is returned as a single
+/// entry when it's a file rather than a directory.
+fn collect_files(source: &Path) -> Result<Vec<(PathBuf, u64)>, BuilderError> {
+    let mut files = Vec::new();
+    if source.is_dir() {
+        collect_dir(source, &mut files)?;
+        files.sort();
+    } else {
+        let length = fs::metadata(source)?.len();
+        files.push((source.to_path_buf(), length));
+    }
+    Ok(files)
+}
+
+fn collect_dir(dir: &Path, files: &mut Vec<(PathBuf, u64)>) -> Result<(), BuilderError> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if path.is_dir() {
+            collect_dir(&path, files)?;
+        } else {
+            files.push((path.clone(), entry.metadata()?.len()));
+        }
+    }
+    Ok(())
+}
+
+/// Reads `files` in order as one contiguous logical stream, splits it into
+/// `piece_length` chunks, and SHA1-hashes each chunk, mirroring how
+/// [`crate::verify`] reads the same stream back for verification.
+fn hash_pieces(files: &[(PathBuf, u64)], piece_length: u64) -> Result<Vec<u8>, BuilderError> {
+    let piece_length = piece_length as usize;
+    let mut pieces = Vec::new();
+    let mut buffer = Vec::with_capacity(piece_length);
+    let mut chunk = vec![0u8; 64 * 1024];
+
+    for (path, _) in files {
+        let mut file = fs::File::open(path)?;
+        loop {
+            let read = file.read(&mut chunk)?;
+            if read == 0 {
+                break;
+            }
+            buffer.extend_from_slice(&chunk[..read]);
+            while buffer.len() >= piece_length {
+                let piece: Vec<u8> = buffer.drain(..piece_length).collect();
+                pieces.extend_from_slice(hash_piece(&piece).as_slice());
+            }
+        }
+    }
+    if !buffer.is_empty() {
+        pieces.extend_from_slice(hash_piece(&buffer).as_slice());
+    }
+    Ok(pieces)
+}
+
+fn hash_piece(data: &[u8]) -> [u8; 20] {
+    let mut hasher = Sha1::new();
+    hasher.update(data);
+    hasher.finalize().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_torrent_has_a_non_trivial_info_hash() {
+        let dir = std::env::temp_dir().join("rustorrent-parser-builder-test");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("file.txt"), b"hello world").unwrap();
+
+        let file = TorrentBuilder::new().build(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        let empty_sha1_hash = {
+            let mut hasher = Sha1::new();
+            hasher.update([]);
+            let digest: [u8; 20] = hasher.finalize().into();
+            digest
+        };
+        assert_ne!(file.info_hash_bytes(), empty_sha1_hash);
+    }
+
+    #[test]
+    fn pick_piece_length_stays_at_the_minimum_for_small_content() {
+        assert_eq!(pick_piece_length(0), 16 * 1024);
+        assert_eq!(pick_piece_length(16 * 1024 * 1500), 16 * 1024);
+    }
+
+    #[test]
+    fn pick_piece_length_doubles_to_keep_the_piece_count_bounded() {
+        // `total_len / piece_length` floors, so the count only exceeds 1500
+        // once `total_len` reaches one full extra piece past the threshold.
+        assert_eq!(pick_piece_length(16 * 1024 * 1501), 32 * 1024);
+    }
+
+    #[test]
+    fn pick_piece_length_never_exceeds_the_maximum() {
+        assert_eq!(pick_piece_length(u64::MAX), 16 * 1024 * 1024);
+    }
+
+    #[test]
+    fn build_rejects_a_zero_piece_length_instead_of_hanging() {
+        let dir = std::env::temp_dir().join("rustorrent-parser-builder-test-zero-piece-length");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("file.txt"), b"hello world").unwrap();
+
+        let result = TorrentBuilder::new().piece_length(0).build(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(result, Err(BuilderError::InvalidPieceLength)));
+    }
+
+    #[test]
+    fn built_torrent_reports_files_under_their_relative_path() {
+        let dir = std::env::temp_dir().join("rustorrent-parser-builder-test-relative-path");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("sub").join("file.txt"), b"hello world").unwrap();
+
+        let file = TorrentBuilder::new().build(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            file.info.files(),
+            vec![(PathBuf::from("sub/file.txt"), 11)]
+        );
+    }
+}
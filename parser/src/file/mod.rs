@@ -1,62 +1,15 @@
-/// Represents the structure of a parsed BitTorrent `.torrent` file.
-/// The `TorrentFile` structure includes metadata about the torrent file itself,
-/// such as the announce URL, creation date, and information about the files in
-/// the torrent.
-///
-/// This struct is deserialized from the bencoded representation of a `.torrent`
-/// file.
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
-pub struct TorrentFile {
-    /// The main tracker URL for the torrent
-    pub announce: Option<String>,
-
-    /// List of backup trackers (multi-tiered tracker support)
-    #[serde(default, rename = "announce-list")]
-    pub announce_list: Vec<Vec<String>>,
-
-    /// Unix timestamp of when the torrent was created
-    #[serde(default, rename = "creation date")]
-    pub creation_date: Option<i64>,
-
-    /// Comment embedded in the torrent (e.g. website or notes)
-    #[serde(default)]
-    pub comment: Option<String>,
-
-    /// Creator client or tool name (e.g. "mktorrent")
-    #[serde(default, rename = "created by")]
-    pub created_by: Option<String>,
-
-    /// Text encoding of strings (usually "UTF-8")
-    #[serde(default)]
-    pub encoding: Option<String>,
+//! Parses the `info` dictionary of a `.torrent` file, in its v1, v2, and
+//! hybrid forms (BEP 3 and BEP 52).
 
-    /// The core metadata dictionary used to identify and download files
-    pub info: TorrentInfo,
-}
+pub mod v1;
+pub mod v2;
+pub mod v2hybrid;
 
-impl TorrentFile {
-    /// Parse a `TorrentFile` from the raw bytes of a `.torrent` file (in
-    /// bencoded format).
-    ///
-    /// # Parameters
-    ///
-    /// * `data`: The raw bytes representing a `.torrent` file in bencoded
-    ///   format.
-    ///
-    /// # Returns
-    ///
-    /// A `Result` containing either a parsed `TorrentFile` or a
-    /// `serde_bencode::Error` if the parsing fails.
-    pub fn from_bytes(data: &[u8]) -> serde_bencode::Result<Self> {
-        serde_bencode::from_bytes(data)
-    }
-}
+use std::path::PathBuf;
 
-/// Represents the `info` dictionary within a `.torrent` file, which contains
-/// metadata about the files being shared, including the file names, sizes,
-/// piece length, and hash information.
+/// Fields shared by the v1, v2, and hybrid `info` dictionaries.
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
-pub struct TorrentInfo {
+pub struct TorrentInfoBase {
     /// The name of the file or directory (used as the base path)
     pub name: String,
 
@@ -64,128 +17,105 @@ pub struct TorrentInfo {
     #[serde(rename = "piece length")]
     pub piece_length: u64,
 
-    /// Concatenated SHA1 hashes of each piece (20 bytes per piece)
-    #[serde(with = "serde_bytes")]
-    pub pieces: serde_bytes::ByteBuf,
-
     /// 1 if private torrent (disables DHT/PEX)
     #[serde(default)]
     pub private: Option<u8>,
 
-    /// MD5 checksum of file (rarely used; deprecated)
+    /// MD5 checksum of file (rarely used; deprecated, v1 only)
     #[serde(default)]
     pub md5sum: Option<String>,
-
-    /// Either a single file or a list of files (multi-file mode)
-    #[serde(flatten)]
-    pub content: TorrentInfoContent,
 }
 
-/// Enum representing the content of the torrent. It can be either a single file
-/// or multiple files.
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
-#[serde(untagged)]
-pub enum TorrentInfoContent {
-    /// For single-file torrents: includes total length (and optional md5sum)
-    Single {
-        length: u64,
-
-        #[serde(default)]
-        md5sum: Option<String>,
-    },
-
-    /// For multi-file torrents: list of files with individual metadata
-    Multi { files: Vec<TorrentFileEntry> },
+/// Which BitTorrent metadata version(s) a torrent provides.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Version {
+    /// Only the v1 (BEP 3) `pieces`/`files` layout is present.
+    V1,
+    /// Only the v2 (BEP 52) `meta version`/`file tree` layout is present.
+    V2,
+    /// Both layouts are present, so v1 and v2 peers can both be served.
+    Hybrid,
 }
 
-/// Represents a single file within a multi-file torrent, including metadata
-/// like file length and path.
+/// The `info` dictionary of a `.torrent` file, in any of the three shapes it
+/// may take on the wire.
+///
+/// Variants are tried most-specific first: a dictionary satisfying both the
+/// v1 and v2 required fields parses as [`TorrentInfo::Hybrid`] rather than
+/// matching one of the single-version variants.
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
-pub struct TorrentFileEntry {
-    /// Size of the file in bytes
-    pub length: u64,
-
-    /// Path components (e.g. ["folder", "file.txt"])
-    pub path: Vec<String>,
-
-    /// MD5 checksum for this file (rarely used)
-    #[serde(default)]
-    pub md5sum: Option<String>,
+#[serde(untagged)]
+pub enum TorrentInfo {
+    Hybrid(v2hybrid::TorrentInfo),
+    V2(v2::TorrentInfo),
+    V1(v1::TorrentInfo),
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn should_parse_v1_multifile() {
-        let torrent = std::fs::read("asset/academictorrent-multifile.torrent").unwrap();
-        let file = TorrentFile::from_bytes(&torrent).unwrap();
-        assert_eq!(
-            file.announce.as_deref(),
-            Some("https://academictorrents.com/announce.php")
-        );
-        assert_eq!(file.info.name, "test_folder");
-
-        assert_eq!(file.info.piece_length, 32768);
-        let TorrentInfoContent::Multi { files } = file.info.content else {
-            panic!("should be multi files");
-        };
-
-        assert_eq!(files[0].length, 17614527);
-        assert_eq!(
-            files[0].path,
-            vec!["images", "LOC_Main_Reading_Room_Highsmith.jpg"]
-        );
-        assert_eq!(files[1].length, 1682177);
-        assert_eq!(files[1].path, vec!["images", "melk-abbey-library.jpg"]);
-        assert_eq!(files[2].length, 20);
-        assert_eq!(files[2].path, vec!["README"]);
+impl TorrentInfo {
+    /// The `name` field common to every version of the `info` dictionary.
+    pub fn name(&self) -> &str {
+        &self.base().name
     }
 
-    #[test]
-    fn should_parse_v1_singlefile() {
-        let torrent = std::fs::read("asset/ubuntu-25.04-desktop-amd64.iso.torrent").unwrap();
-        let file = TorrentFile::from_bytes(&torrent).unwrap();
-        assert_eq!(
-            file.announce.as_deref(),
-            Some("https://torrent.ubuntu.com/announce")
-        );
-        assert_eq!(file.announce_list.len(), 2);
-        assert_eq!(file.creation_date, Some(1744895485));
-        assert_eq!(
-            file.comment.as_deref(),
-            Some("Ubuntu CD releases.ubuntu.com")
-        );
-        assert_eq!(file.created_by.as_deref(), Some("mktorrent 1.1"));
-        assert_eq!(file.encoding, None);
-        assert_eq!(file.info.name, "ubuntu-25.04-desktop-amd64.iso");
+    /// The `piece length` field common to every version of the `info`
+    /// dictionary.
+    pub fn piece_length(&self) -> u64 {
+        self.base().piece_length
+    }
 
-        assert_eq!(file.info.piece_length, 262144);
-        let TorrentInfoContent::Single { length, md5sum: _ } = file.info.content else {
-            panic!("should be single file");
-        };
+    fn base(&self) -> &TorrentInfoBase {
+        match self {
+            Self::Hybrid(inner) => &inner.base,
+            Self::V2(inner) => &inner.base,
+            Self::V1(inner) => &inner.base,
+        }
+    }
 
-        assert_eq!(length, 6278520832);
+    /// Reports whether this torrent carries v1 metadata, v2 metadata, or
+    /// both.
+    pub fn version(&self) -> Version {
+        match self {
+            Self::Hybrid(_) => Version::Hybrid,
+            Self::V2(_) => Version::V2,
+            Self::V1(_) => Version::V1,
+        }
     }
 
-    #[test]
-    fn should_parse_v2_hybrid() {
-        let torrent = std::fs::read("asset/bittorrent-v2-hybrid-test.torrent").unwrap();
-        let file = TorrentFile::from_bytes(&torrent).unwrap();
-        assert_eq!(file.announce, None);
-        assert!(file.announce_list.is_empty());
-        assert_eq!(file.creation_date, Some(1591173906));
-        assert_eq!(file.comment, None);
-        assert_eq!(file.created_by.as_deref(), Some("libtorrent"));
-        assert_eq!(file.encoding, None);
-        assert_eq!(file.info.name, "bittorrent-v1-v2-hybrid-test");
+    /// The v1 (BEP 3) fields, if this torrent carries them.
+    pub fn as_v1(&self) -> Option<&v1::TorrentInfoFields> {
+        match self {
+            Self::Hybrid(inner) => Some(&inner.v1),
+            Self::V2(_) => None,
+            Self::V1(inner) => Some(&inner.fields),
+        }
+    }
 
-        assert_eq!(file.info.piece_length, 524288);
-        let TorrentInfoContent::Multi { files } = file.info.content else {
-            panic!("should be multiple files");
-        };
+    /// The v2 (BEP 52) fields, if this torrent carries them.
+    pub fn as_v2(&self) -> Option<&v2::TorrentInfoFields> {
+        match self {
+            Self::Hybrid(inner) => Some(&inner.v2),
+            Self::V2(inner) => Some(&inner.fields),
+            Self::V1(_) => None,
+        }
+    }
 
-        assert_eq!(files.len(), 17);
+    /// Lists every file described by this torrent, regardless of version,
+    /// paired with its length in bytes.
+    ///
+    /// For v1 and hybrid torrents this reflects the `files`/single-file
+    /// layout; for v2-only torrents it's derived by walking the `file tree`.
+    /// For hybrid torrents the two layouts describe the same files, so
+    /// either one could be used; the v1 layout is preferred since it
+    /// preserves the declared file order.
+    pub fn files(&self) -> Vec<(PathBuf, u64)> {
+        if let Some(v1) = self.as_v1() {
+            v1.file_iter(&self.base().name).collect()
+        } else if let Some(v2) = self.as_v2() {
+            v2.file_iter()
+                .map(|(path, entry)| (path, entry.length))
+                .collect()
+        } else {
+            Vec::new()
+        }
     }
 }
@@ -1,7 +1,6 @@
 use std::path::PathBuf;
 
-/// This section contains the field which are common to both mode, "single file"
-/// and "multiple file".
+/// The `info` dictionary of a v1-only torrent (BEP 3).
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct TorrentInfo {
     #[serde(flatten)]
@@ -25,26 +24,37 @@ pub struct TorrentInfoFields {
 }
 
 impl TorrentInfoFields {
-    pub fn file_iter(&self, base_name: &str) -> impl Iterator<Item = PathBuf> + '_ {
+    /// Iterates over every file in this torrent, in declared order, yielding
+    /// each file's path together with its length in bytes. A single-file
+    /// torrent yields `base_name` itself as the path; a multi-file torrent
+    /// yields each path relative to the `base_name` directory.
+    pub fn file_iter<'a>(
+        &'a self,
+        base_name: &'a str,
+    ) -> impl Iterator<Item = (PathBuf, u64)> + 'a {
         match self.content {
-            TorrentInfoContent::File { .. } => {
-                TorrentIterator::Single(std::iter::once(PathBuf::from(base_name)))
+            TorrentInfoContent::File { length, .. } => {
+                TorrentIterator::Single(std::iter::once((PathBuf::from(base_name), length)))
             }
             TorrentInfoContent::Directory { ref files } => {
-                TorrentIterator::Multi(files.iter().map(TorrentFileEntry::path))
+                TorrentIterator::Multi(files.iter().map(|entry| (entry.path(), entry.length)))
             }
         }
     }
 }
+/// Iterator yielding `(path, length)` for each entry in a directory's file
+/// list, named so [`TorrentIterator::Multi`] doesn't repeat it inline (which
+/// trips `clippy::type_complexity`).
+type MultiFileIter<'a> =
+    std::iter::Map<std::slice::Iter<'a, TorrentFileEntry>, fn(&'a TorrentFileEntry) -> (PathBuf, u64)>;
+
 enum TorrentIterator<'a> {
-    Single(std::iter::Once<PathBuf>),
-    Multi(
-        std::iter::Map<std::slice::Iter<'a, TorrentFileEntry>, fn(&'a TorrentFileEntry) -> PathBuf>,
-    ),
+    Single(std::iter::Once<(PathBuf, u64)>),
+    Multi(MultiFileIter<'a>),
 }
 
 impl Iterator for TorrentIterator<'_> {
-    type Item = PathBuf;
+    type Item = (PathBuf, u64);
 
     fn next(&mut self) -> Option<Self::Item> {
         match self {
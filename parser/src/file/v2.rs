@@ -1,8 +1,12 @@
 use std::collections::BTreeMap;
 use std::path::PathBuf;
 
-/// This section contains the field which are common to both mode, "single file"
-/// and "multiple file".
+use sha2::{Digest, Sha256};
+
+/// Size of a leaf block in a v2 file's Merkle tree (BEP 52).
+const BLOCK_SIZE: usize = 16 * 1024;
+
+/// The `info` dictionary of a v2-only torrent (BEP 52).
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct TorrentInfo {
     #[serde(flatten)]
@@ -101,3 +105,148 @@ pub struct TorrentFileEntry {
     #[serde(default)]
     pub md5sum: Option<String>,
 }
+
+impl TorrentFileEntry {
+    /// Splits `data` into 16 KiB leaf blocks (the final one hashed over its
+    /// actual length, not zero-padded), hashes each with SHA-256, and builds
+    /// a balanced binary Merkle tree over them (BEP 52). Returns every layer,
+    /// from the leaves (index `0`) up to the root (the last, single-element
+    /// layer). The leaf layer is padded up to the next power of two with the
+    /// hash of a zero-filled block (per BEP 52), not zero bytes themselves,
+    /// so every layer but the root has even width; a file smaller than one block
+    /// still produces a single leaf, promoted directly to the root.
+    pub fn merkle_layers(data: &[u8]) -> Vec<Vec<[u8; 32]>> {
+        let mut leaves: Vec<[u8; 32]> = if data.is_empty() {
+            vec![Sha256::new().finalize().into()]
+        } else {
+            data.chunks(BLOCK_SIZE)
+                .map(|block| {
+                    let mut hasher = Sha256::new();
+                    hasher.update(block);
+                    hasher.finalize().into()
+                })
+                .collect()
+        };
+        let pad_hash: [u8; 32] = Sha256::digest([0u8; BLOCK_SIZE]).into();
+        leaves.resize(leaves.len().next_power_of_two(), pad_hash);
+
+        let mut layers = vec![leaves];
+        while layers.last().expect("layers is never empty").len() > 1 {
+            let next = layers
+                .last()
+                .expect("layers is never empty")
+                .chunks(2)
+                .map(|pair| {
+                    let mut hasher = Sha256::new();
+                    hasher.update(pair[0]);
+                    hasher.update(pair[1]);
+                    hasher.finalize().into()
+                })
+                .collect();
+            layers.push(next);
+        }
+        layers
+    }
+
+    /// Computes `data`'s SHA-256 Merkle root the same way as
+    /// [`TorrentFileEntry::merkle_layers`] and checks it against this file's
+    /// recorded [`TorrentFileEntry::pieces_root`].
+    pub fn verify_merkle_root(&self, data: &[u8]) -> bool {
+        let layers = Self::merkle_layers(data);
+        let Some(root) = layers.last().and_then(|layer| layer.first()) else {
+            return false;
+        };
+        root.as_slice() == self.pieces_root.as_slice()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(data: &[u8]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(data);
+        hasher.finalize().into()
+    }
+
+    fn combine(left: [u8; 32], right: [u8; 32]) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(left);
+        hasher.update(right);
+        hasher.finalize().into()
+    }
+
+    fn entry(pieces_root: [u8; 32]) -> TorrentFileEntry {
+        TorrentFileEntry {
+            length: 0,
+            pieces_root: serde_bytes::ByteBuf::from(pieces_root.to_vec()),
+            md5sum: None,
+        }
+    }
+
+    #[test]
+    fn merkle_layers_of_empty_data_is_a_single_leaf_hashing_nothing() {
+        let layers = TorrentFileEntry::merkle_layers(&[]);
+        assert_eq!(layers, vec![vec![hash(&[])]]);
+    }
+
+    #[test]
+    fn merkle_layers_of_sub_block_data_is_a_single_leaf() {
+        let data = vec![7u8; BLOCK_SIZE - 1];
+        let layers = TorrentFileEntry::merkle_layers(&data);
+        assert_eq!(layers, vec![vec![hash(&data)]]);
+    }
+
+    #[test]
+    fn merkle_layers_of_two_blocks_builds_a_two_level_tree() {
+        let first = vec![1u8; BLOCK_SIZE];
+        let second = vec![2u8; BLOCK_SIZE];
+        let data: Vec<u8> = first.iter().chain(&second).copied().collect();
+
+        let layers = TorrentFileEntry::merkle_layers(&data);
+        let leaves = vec![hash(&first), hash(&second)];
+        let root = combine(leaves[0], leaves[1]);
+        assert_eq!(layers, vec![leaves, vec![root]]);
+    }
+
+    #[test]
+    fn merkle_layers_of_three_blocks_pads_with_the_zero_block_hash() {
+        let first = vec![1u8; BLOCK_SIZE];
+        let second = vec![2u8; BLOCK_SIZE];
+        let third = vec![3u8; BLOCK_SIZE / 2];
+        let data: Vec<u8> = first.iter().chain(&second).chain(&third).copied().collect();
+
+        let layers = TorrentFileEntry::merkle_layers(&data);
+        let pad = hash(&[0u8; BLOCK_SIZE]);
+        let leaves = vec![hash(&first), hash(&second), hash(&third), pad];
+        let inner = vec![combine(leaves[0], leaves[1]), combine(leaves[2], leaves[3])];
+        let root = combine(inner[0], inner[1]);
+        assert_eq!(layers, vec![leaves, inner, vec![root]]);
+    }
+
+    #[test]
+    fn verify_merkle_root_accepts_the_matching_root() {
+        let data = vec![9u8; BLOCK_SIZE + 1];
+        let root = *TorrentFileEntry::merkle_layers(&data)
+            .last()
+            .unwrap()
+            .first()
+            .unwrap();
+        assert!(entry(root).verify_merkle_root(&data));
+    }
+
+    #[test]
+    fn verify_merkle_root_rejects_tampered_data() {
+        let data = vec![9u8; BLOCK_SIZE + 1];
+        let root = *TorrentFileEntry::merkle_layers(&data)
+            .last()
+            .unwrap()
+            .first()
+            .unwrap();
+
+        let mut tampered = data;
+        tampered[0] ^= 0xFF;
+        assert!(!entry(root).verify_merkle_root(&tampered));
+    }
+}
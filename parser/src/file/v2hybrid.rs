@@ -1,3 +1,8 @@
+use std::path::PathBuf;
+
+/// The `info` dictionary of a hybrid torrent, which carries both the v1
+/// (BEP 3) and v2 (BEP 52) fields so it can be served to peers of either
+/// version.
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct TorrentInfo {
     #[serde(flatten)]
@@ -7,3 +12,20 @@ pub struct TorrentInfo {
     #[serde(flatten)]
     pub v2: super::v2::TorrentInfoFields,
 }
+
+impl TorrentInfo {
+    /// Checks that the v1 `files` listing and the v2 `file tree` describe the
+    /// same set of files (same relative path and length), which BEP 52
+    /// requires for a well-formed hybrid torrent.
+    pub fn reconcile(&self) -> bool {
+        let mut v1_files: Vec<(PathBuf, u64)> = self.v1.file_iter(&self.base.name).collect();
+        let mut v2_files: Vec<(PathBuf, u64)> = self
+            .v2
+            .file_iter()
+            .map(|(path, entry)| (path, entry.length))
+            .collect();
+        v1_files.sort();
+        v2_files.sort();
+        v1_files == v2_files
+    }
+}
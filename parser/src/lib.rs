@@ -1,6 +1,18 @@
 #![doc = include_str!("../readme.md")]
 
-use std::str::FromStr;
+use std::collections::BTreeMap;
+
+use sha1::{Digest, Sha1};
+use sha2::Sha256;
+
+mod bencode;
+pub mod builder;
+pub mod file;
+pub mod magnet;
+pub mod tracker;
+pub mod verify;
+
+pub use file::TorrentInfo;
 
 /// Represents the structure of a parsed BitTorrent `.torrent` file.
 /// The `TorrentFile` structure includes metadata about the torrent file itself,
@@ -11,8 +23,10 @@ use std::str::FromStr;
 /// file.
 #[derive(Debug, serde::Deserialize, serde::Serialize)]
 pub struct TorrentFile {
-    /// The main tracker URL for the torrent
-    pub announce: String,
+    /// The main tracker URL for the torrent. Optional, since DHT/PEX-only
+    /// torrents (common for v2 and hybrid torrents) may not carry a tracker.
+    #[serde(default)]
+    pub announce: Option<String>,
 
     /// List of backup trackers (multi-tiered tracker support)
     #[serde(default, rename = "announce-list")]
@@ -36,6 +50,19 @@ pub struct TorrentFile {
 
     /// The core metadata dictionary used to identify and download files
     pub info: TorrentInfo,
+
+    /// For v2/hybrid torrents (BEP 52): maps each file's 32-byte SHA-256
+    /// `pieces root` merkle root to the concatenated SHA-256 hashes that make
+    /// up that file's second merkle tree layer.
+    #[serde(default, rename = "piece layers")]
+    pub piece_layers: BTreeMap<serde_bytes::ByteBuf, serde_bytes::ByteBuf>,
+
+    /// The exact bencoded bytes of the `info` dictionary, as they appeared in
+    /// the original file. Captured by [`TorrentFile::from_bytes`] and used by
+    /// [`TorrentFile::info_hash_bytes`]; not part of the torrent format
+    /// itself.
+    #[serde(skip)]
+    raw_info: Vec<u8>,
 }
 
 impl TorrentFile {
@@ -52,246 +79,117 @@ impl TorrentFile {
     /// A `Result` containing either a parsed `TorrentFile` or a
     /// `serde_bencode::Error` if the parsing fails.
     pub fn from_bytes(data: &[u8]) -> serde_bencode::Result<Self> {
-        serde_bencode::from_bytes(data)
+        let mut file: TorrentFile = serde_bencode::from_bytes(data)?;
+        if let Some(raw_info) = bencode::locate_top_level_key(data, b"info") {
+            file.raw_info = raw_info.to_vec();
+        }
+        Ok(file)
     }
-}
-
-/// Represents the `info` dictionary within a `.torrent` file, which contains
-/// metadata about the files being shared, including the file names, sizes,
-/// piece length, and hash information.
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
-pub struct TorrentInfo {
-    /// The name of the file or directory (used as the base path)
-    pub name: String,
-
-    /// Piece size in bytes (each file is split into pieces of this length)
-    #[serde(rename = "piece length")]
-    pub piece_length: u64,
-
-    /// Concatenated SHA1 hashes of each piece (20 bytes per piece)
-    #[serde(with = "serde_bytes")]
-    pub pieces: serde_bytes::ByteBuf,
-
-    /// 1 if private torrent (disables DHT/PEX)
-    #[serde(default)]
-    pub private: Option<u8>,
-
-    /// MD5 checksum of file (rarely used; deprecated)
-    #[serde(default)]
-    pub md5sum: Option<String>,
-
-    /// Either a single file or a list of files (multi-file mode)
-    #[serde(flatten)]
-    pub content: TorrentInfoContent,
-}
-
-/// Enum representing the content of the torrent. It can be either a single file
-/// or multiple files.
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
-#[serde(untagged)]
-pub enum TorrentInfoContent {
-    /// For single-file torrents: includes total length (and optional md5sum)
-    Single {
-        length: u64,
-
-        #[serde(default)]
-        md5sum: Option<String>,
-    },
-
-    /// For multi-file torrents: list of files with individual metadata
-    Multi { files: Vec<TorrentFileEntry> },
-}
 
-/// Represents a single file within a multi-file torrent, including metadata
-/// like file length and path.
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
-pub struct TorrentFileEntry {
-    /// Size of the file in bytes
-    pub length: u64,
+    /// Serializes this torrent back into its bencoded `.torrent` form.
+    ///
+    /// Note that re-parsing the result with [`TorrentFile::from_bytes`] is
+    /// not guaranteed to reproduce the exact same `info_hash` as the
+    /// original file this `TorrentFile` was parsed from, since this
+    /// re-serializes [`TorrentInfo`] rather than reusing the original raw
+    /// bytes; this matters when round-tripping an already-parsed torrent,
+    /// though [`builder::TorrentBuilder`] populates its own `raw_info`
+    /// directly from this method's output, so its `info_hash` is correct as
+    /// soon as it's built.
+    pub fn to_bytes(&self) -> serde_bencode::Result<Vec<u8>> {
+        serde_bencode::to_bytes(self)
+    }
 
-    /// Path components (e.g. ["folder", "file.txt"])
-    pub path: Vec<String>,
+    /// Returns the torrent's info_hash: the SHA1 digest of the exact bencoded
+    /// bytes of the `info` dictionary as they appeared in the original
+    /// `.torrent` file.
+    ///
+    /// The hash is computed over those raw bytes rather than a
+    /// re-serialization of [`TorrentInfo`], since a serde round-trip can
+    /// reorder keys or drop fields this parser doesn't know about and would
+    /// silently produce a hash that trackers and peers would reject.
+    pub fn info_hash_bytes(&self) -> [u8; 20] {
+        let mut hasher = Sha1::new();
+        hasher.update(&self.raw_info);
+        hasher.finalize().into()
+    }
 
-    /// MD5 checksum for this file (rarely used)
-    #[serde(default)]
-    pub md5sum: Option<String>,
-}
+    /// Hex-encoded form of [`TorrentFile::info_hash_bytes`].
+    pub fn info_hash_hex(&self) -> String {
+        hex::encode(self.info_hash_bytes())
+    }
 
-/// Represents errors that may occur while parsing magnet links.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub enum MagnetLinkParserError {
-    InvalidUrl(url::ParseError),
-    InvalidScheme,
-    MissingInfoHash,
-}
+    /// Returns the v2 (BEP 52) info_hash: the SHA-256 digest of the exact
+    /// bencoded bytes of the `info` dictionary, computed the same way as
+    /// [`TorrentFile::info_hash_bytes`] but with SHA-256 instead of SHA1.
+    ///
+    /// This is only meaningful for [`file::Version::V2`] and
+    /// [`file::Version::Hybrid`] torrents; it's still derivable for v1-only
+    /// torrents, but peers and trackers won't recognize it.
+    pub fn info_hash_v2_bytes(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.raw_info);
+        hasher.finalize().into()
+    }
 
-impl From<url::ParseError> for MagnetLinkParserError {
-    fn from(value: url::ParseError) -> Self {
-        Self::InvalidUrl(value)
+    /// Hex-encoded form of [`TorrentFile::info_hash_v2_bytes`].
+    pub fn info_hash_v2_hex(&self) -> String {
+        hex::encode(self.info_hash_v2_bytes())
     }
-}
 
-impl std::fmt::Display for MagnetLinkParserError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::InvalidUrl(inner) => inner.fmt(f),
-            Self::InvalidScheme => write!(f, "invalid scheme, expected \"magnet\""),
-            Self::MissingInfoHash => write!(f, "missing xt parameter for info_hash attribute"),
-        }
+    /// The v2 info_hash truncated to 20 bytes, for BEP 52's "v2-as-v1"
+    /// compatibility: software that only understands the 20-byte v1
+    /// identifier can still use this to refer to a v2-only torrent, at the
+    /// cost of losing collision resistance below 160 bits.
+    pub fn info_hash_v2_truncated_bytes(&self) -> [u8; 20] {
+        let full = self.info_hash_v2_bytes();
+        let mut truncated = [0u8; 20];
+        truncated.copy_from_slice(&full[..20]);
+        truncated
     }
-}
 
-impl std::error::Error for MagnetLinkParserError {}
-
-/// Represents a parsed Magnet URI, which includes the info hash, display name,
-/// trackers, and web seeds.
-#[derive(Debug)]
-pub struct MagnetLink {
-    /// The 40-character hexadecimal BitTorrent info hash (unique identifier for
-    /// the torrent).
-    pub info_hash: String,
-    /// A human-readable display name (e.g. for UI display).
-    pub display_name: Option<String>,
-    /// List of tracker URLs (announces) provided in the URI.
-    pub trackers: Vec<String>,
-    /// List of web seed URLs from the `ws` parameter (for HTTP-based seeding).
-    pub web_seeds: Vec<String>,
-    /// Any additional parameters (e.g. web seeds, peer sources, etc).
-    pub params: Vec<(String, String)>,
+    /// Hex-encoded form of [`TorrentFile::info_hash_v2_truncated_bytes`].
+    pub fn info_hash_v2_truncated_hex(&self) -> String {
+        hex::encode(self.info_hash_v2_truncated_bytes())
+    }
 }
 
-impl FromStr for MagnetLink {
-    type Err = MagnetLinkParserError;
-
-    fn from_str(uri: &str) -> Result<Self, MagnetLinkParserError> {
-        let url = url::Url::parse(uri)?;
-        if url.scheme() != "magnet" {
-            return Err(MagnetLinkParserError::InvalidScheme);
-        }
-
-        let mut info_hash = None;
-        let mut display_name = None;
-        let mut trackers = Vec::new();
-        let mut web_seeds = Vec::new();
-        let mut params = Vec::new();
-
-        for (key, value) in url.query_pairs() {
-            match key.as_ref() {
-                "xt" if value.starts_with("urn:btih:") => {
-                    info_hash = Some(value.trim_start_matches("urn:btih:").into());
-                }
-                "dn" => {
-                    display_name = Some(value.into());
-                }
-                "tr" => {
-                    trackers.push(value.into());
-                }
-                "ws" => {
-                    web_seeds.push(value.into());
-                }
-                _ => {
-                    params.push((key.into(), value.into()));
-                }
+impl TorrentFile {
+    /// Builds a magnet link pointing at this torrent, carrying a `urn:btih:`
+    /// entry for v1 and hybrid torrents and a `urn:btmh:` entry for v2 and
+    /// hybrid torrents, plus its display name and trackers.
+    pub fn to_magnet(&self) -> magnet::MagnetLink {
+        let mut hashes = Vec::new();
+        match self.info.version() {
+            file::Version::V1 => {
+                hashes.push(magnet::InfoHash::V1(self.info_hash_hex()));
+            }
+            file::Version::V2 => {
+                hashes.push(magnet::InfoHash::V2(magnet::encode_v2_multihash(
+                    &self.info_hash_v2_bytes(),
+                )));
+            }
+            file::Version::Hybrid => {
+                hashes.push(magnet::InfoHash::V1(self.info_hash_hex()));
+                hashes.push(magnet::InfoHash::V2(magnet::encode_v2_multihash(
+                    &self.info_hash_v2_bytes(),
+                )));
             }
         }
 
-        let info_hash = info_hash.ok_or(MagnetLinkParserError::MissingInfoHash)?;
+        let trackers = self
+            .announce
+            .iter()
+            .cloned()
+            .chain(self.announce_list.iter().flatten().cloned())
+            .collect();
 
-        Ok(MagnetLink {
-            info_hash,
-            display_name,
+        magnet::MagnetLink {
+            hashes,
+            display_name: Some(self.info.name().to_string()),
             trackers,
-            web_seeds,
-            params,
-        })
-    }
-}
-
-/// Represents errors when processing hash bytes (info hashes).
-#[derive(Debug, PartialEq)]
-pub enum HashBytesError {
-    UnsupportedLength,
-    InvalidHex(hex::FromHexError),
-    InvalidBase32,
-    InvalidLength,
-}
-
-impl std::fmt::Display for HashBytesError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match self {
-            Self::UnsupportedLength => write!(f, "unsupported info_hash length"),
-            Self::InvalidHex(_) => write!(f, "invalid hex info_hash"),
-            Self::InvalidBase32 => write!(f, "invalid base32 info_hash"),
-            Self::InvalidLength => write!(f, "invalid SHA1 hash length, expected 20 bytes"),
-        }
-    }
-}
-
-impl std::error::Error for HashBytesError {}
-
-impl MagnetLink {
-    /// Converts the `info_hash` (a hexadecimal or Base32 string) to a 20-byte
-    /// SHA1 hash.
-    ///
-    /// This function will take the `info_hash` from the magnet link, which can
-    /// be provided in either hexadecimal or Base32 encoding, and convert it
-    /// to a fixed-length 20-byte array representing the SHA1 hash.
-    ///
-    /// # Returns
-    ///
-    /// Returns a `Result`:
-    /// - `Ok([u8; 20])`: A 20-byte array representing the decoded SHA1 hash of
-    ///   the torrent info hash.
-    /// - `Err(HashBytesError)`: An error if the `info_hash` is of an
-    ///   unsupported length, has invalid hexadecimal characters, or is
-    ///   improperly formatted in Base32.
-    ///
-    /// # Errors
-    ///
-    /// This function may return the following errors:
-    ///
-    /// - `HashBytesError::UnsupportedLength`: The `info_hash` is neither 32 nor
-    ///   40 characters in length.
-    /// - `HashBytesError::InvalidHex`: The `info_hash` contains invalid
-    ///   hexadecimal characters when the input is expected to be hexadecimal.
-    /// - `HashBytesError::InvalidBase32`: The `info_hash` is in an invalid
-    ///   Base32 format when the input is expected to be Base32.
-    /// - `HashBytesError::InvalidLength`: The decoded value is not 20 bytes in
-    ///   length, which is the expected size for a SHA1 hash.
-    ///
-    /// # Example
-    ///
-    /// ```rust
-    /// use rustorrent_parser::MagnetLink;
-    ///
-    /// let magnet_link = MagnetLink {
-    ///     info_hash: "d6a67b7e10b219d01f84c1c99962f060c18bb658".to_string(),
-    ///     display_name: None,
-    ///     trackers: Vec::new(),
-    ///     web_seeds: Vec::new(),
-    ///     params: Vec::new(),
-    /// };
-    ///
-    /// let hash = magnet_link.hash_bytes();
-    /// assert!(hash.is_ok());
-    /// assert_eq!(hash.unwrap().len(), 20);
-    /// ```
-    pub fn hash_bytes(&self) -> Result<[u8; 20], HashBytesError> {
-        let cleaned = self.info_hash.to_ascii_lowercase();
-
-        if cleaned.len() == 40 {
-            let decoded = hex::decode(&cleaned).map_err(HashBytesError::InvalidHex)?;
-            decoded
-                .try_into()
-                .map_err(|_| HashBytesError::InvalidLength)
-        } else if cleaned.len() == 32 {
-            let decoded = base32::decode(base32::Alphabet::Rfc4648 { padding: false }, &cleaned)
-                .ok_or(HashBytesError::InvalidBase32)?;
-            decoded
-                .try_into()
-                .map_err(|_| HashBytesError::InvalidLength)
-        } else {
-            Err(HashBytesError::UnsupportedLength)
+            web_seeds: Vec::new(),
+            params: Vec::new(),
         }
     }
 }
@@ -304,30 +202,37 @@ mod tests {
     fn should_parse_multifile_torrent() {
         let torrent = include_bytes!("../asset/academictorrent-multifile.torrent");
         let file = TorrentFile::from_bytes(torrent).unwrap();
-        assert_eq!(file.announce, "https://academictorrents.com/announce.php");
-        assert_eq!(file.info.name, "test_folder");
-
-        assert_eq!(file.info.piece_length, 32768);
-        let TorrentInfoContent::Multi { files } = file.info.content else {
-            panic!("should be multi files");
-        };
+        assert_eq!(
+            file.announce.as_deref(),
+            Some("https://academictorrents.com/announce.php")
+        );
+        assert_eq!(file.info.name(), "test_folder");
+        assert_eq!(file.info.piece_length(), 32768);
+        assert_eq!(file.info.version(), file::Version::V1);
 
-        assert_eq!(files[0].length, 17614527);
+        let files = file.info.files();
         assert_eq!(
-            files[0].path,
-            vec!["images", "LOC_Main_Reading_Room_Highsmith.jpg"]
+            files[0],
+            (
+                std::path::PathBuf::from("images/LOC_Main_Reading_Room_Highsmith.jpg"),
+                17614527
+            )
         );
-        assert_eq!(files[1].length, 1682177);
-        assert_eq!(files[1].path, vec!["images", "melk-abbey-library.jpg"]);
-        assert_eq!(files[2].length, 20);
-        assert_eq!(files[2].path, vec!["README"]);
+        assert_eq!(
+            files[1],
+            (std::path::PathBuf::from("images/melk-abbey-library.jpg"), 1682177)
+        );
+        assert_eq!(files[2], (std::path::PathBuf::from("README"), 20));
     }
 
     #[test]
     fn should_parse_ubuntu_torrent() {
         let torrent = include_bytes!("../asset/ubuntu-25.04-desktop-amd64.iso.torrent");
         let file = TorrentFile::from_bytes(torrent).unwrap();
-        assert_eq!(file.announce, "https://torrent.ubuntu.com/announce");
+        assert_eq!(
+            file.announce.as_deref(),
+            Some("https://torrent.ubuntu.com/announce")
+        );
         assert_eq!(file.announce_list.len(), 2);
         assert_eq!(file.creation_date, Some(1744895485));
         assert_eq!(
@@ -336,24 +241,39 @@ mod tests {
         );
         assert_eq!(file.created_by.as_deref(), Some("mktorrent 1.1"));
         assert_eq!(file.encoding, None);
-        assert_eq!(file.info.name, "ubuntu-25.04-desktop-amd64.iso");
+        assert_eq!(file.info.name(), "ubuntu-25.04-desktop-amd64.iso");
+        assert_eq!(file.info.piece_length(), 262144);
+        assert_eq!(file.info.version(), file::Version::V1);
 
-        assert_eq!(file.info.piece_length, 262144);
-        let TorrentInfoContent::Single { length, md5sum: _ } = file.info.content else {
-            panic!("should be single file");
-        };
+        let files = file.info.files();
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].1, 6278520832);
+    }
 
-        assert_eq!(length, 6278520832);
+    #[test]
+    fn should_parse_v2_hybrid_torrent() {
+        let torrent = include_bytes!("../asset/bittorrent-v2-hybrid-test.torrent");
+        let file = TorrentFile::from_bytes(torrent).unwrap();
+        assert_eq!(file.announce, None);
+        assert_eq!(file.created_by.as_deref(), Some("libtorrent"));
+        assert_eq!(file.info.name(), "bittorrent-v1-v2-hybrid-test");
+        assert_eq!(file.info.piece_length(), 524288);
+        assert_eq!(file.info.version(), file::Version::Hybrid);
+        assert_eq!(file.info.files().len(), 17);
+
+        let file::TorrentInfo::Hybrid(ref hybrid) = file.info else {
+            panic!("should be hybrid");
+        };
+        assert!(hybrid.reconcile());
     }
 
     #[test]
-    fn should_parse_academic_link() {
-        let url = "magnet:?xt=urn:btih:d984f67af9917b214cd8b6048ab5624c7df6a07a&tr=https%3A%2F%2Facademictorrents.com%2Fannounce.php&tr=udp%3A%2F%2Ftracker.coppersurfer.tk%3A6969&tr=udp%3A%2F%2Ftracker.opentrackr.org%3A1337%2Fannounce";
-        let magnet = crate::MagnetLink::from_str(url).unwrap();
-        assert_eq!(magnet.info_hash, "d984f67af9917b214cd8b6048ab5624c7df6a07a");
-        assert_eq!(magnet.display_name, None);
-        assert_eq!(magnet.trackers.len(), 3);
-        assert!(magnet.web_seeds.is_empty());
-        assert!(magnet.params.is_empty());
+    fn should_build_magnet_link() {
+        let torrent = include_bytes!("../asset/bittorrent-v2-hybrid-test.torrent");
+        let file = TorrentFile::from_bytes(torrent).unwrap();
+        let magnet = file.to_magnet();
+        assert_eq!(magnet.v1_hash(), Some(file.info_hash_hex().as_str()));
+        assert!(magnet.v2_hash().unwrap().starts_with("1220"));
+        assert_eq!(magnet.display_name.as_deref(), Some(file.info.name()));
     }
 }
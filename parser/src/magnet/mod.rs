@@ -26,20 +26,32 @@ impl std::fmt::Display for ParseError {
 
 impl std::error::Error for ParseError {}
 
-/// Represents a parsed Magnet URI, which includes the info hash, display name,
-/// trackers, and web seeds.
-#[derive(Debug)]
+/// One `xt` (exact topic) entry of a magnet URI identifying a torrent by
+/// hash. A hybrid torrent's magnet carries one of each variant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InfoHash {
+    /// BEP 3 v1 info_hash, as it appeared after `urn:btih:`: either a
+    /// 40-character hex string or a 32-character Base32 string.
+    V1(String),
+    /// BEP 52 v2 info_hash, as it appeared after `urn:btmh:`: a BEP 9
+    /// multihash, hex-encoded (`1220` followed by 64 hex characters for
+    /// SHA-256).
+    V2(String),
+}
+
+/// Represents a parsed Magnet URI, which includes the info hash(es), display
+/// name, trackers, and web seeds.
+#[derive(Debug, Clone)]
 pub struct MagnetLink {
-    /// The 40-character hexadecimal BitTorrent info hash (unique identifier for
-    /// the torrent).
-    pub info_hash: String,
+    /// The `xt` entries carried by this link, in the order they appeared.
+    pub hashes: Vec<InfoHash>,
     /// A human-readable display name (e.g. for UI display).
     pub display_name: Option<String>,
     /// List of tracker URLs (announces) provided in the URI.
     pub trackers: Vec<String>,
     /// List of web seed URLs from the `ws` parameter (for HTTP-based seeding).
     pub web_seeds: Vec<String>,
-    /// Any additional parameters (e.g. web seeds, peer sources, etc).
+    /// Any additional parameters (e.g. peer sources, etc).
     pub params: Vec<(String, String)>,
 }
 
@@ -52,7 +64,7 @@ impl FromStr for MagnetLink {
             return Err(ParseError::InvalidScheme);
         }
 
-        let mut info_hash = None;
+        let mut hashes = Vec::new();
         let mut display_name = None;
         let mut trackers = Vec::new();
         let mut web_seeds = Vec::new();
@@ -61,7 +73,10 @@ impl FromStr for MagnetLink {
         for (key, value) in url.query_pairs() {
             match key.as_ref() {
                 "xt" if value.starts_with("urn:btih:") => {
-                    info_hash = Some(value.trim_start_matches("urn:btih:").into());
+                    hashes.push(InfoHash::V1(value.trim_start_matches("urn:btih:").into()));
+                }
+                "xt" if value.starts_with("urn:btmh:") => {
+                    hashes.push(InfoHash::V2(value.trim_start_matches("urn:btmh:").into()));
                 }
                 "dn" => {
                     display_name = Some(value.into());
@@ -78,10 +93,12 @@ impl FromStr for MagnetLink {
             }
         }
 
-        let info_hash = info_hash.ok_or(ParseError::MissingInfoHash)?;
+        if hashes.is_empty() {
+            return Err(ParseError::MissingInfoHash);
+        }
 
         Ok(MagnetLink {
-            info_hash,
+            hashes,
             display_name,
             trackers,
             web_seeds,
@@ -90,6 +107,12 @@ impl FromStr for MagnetLink {
     }
 }
 
+impl std::fmt::Display for MagnetLink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_uri())
+    }
+}
+
 /// Represents errors when processing hash bytes (info hashes).
 #[derive(Debug, PartialEq)]
 pub enum HashBytesError {
@@ -97,6 +120,8 @@ pub enum HashBytesError {
     InvalidHex(hex::FromHexError),
     InvalidBase32,
     InvalidLength,
+    InvalidMultihash,
+    MissingHash,
 }
 
 impl std::fmt::Display for HashBytesError {
@@ -106,49 +131,67 @@ impl std::fmt::Display for HashBytesError {
             Self::InvalidHex(_) => write!(f, "invalid hex info_hash"),
             Self::InvalidBase32 => write!(f, "invalid base32 info_hash"),
             Self::InvalidLength => write!(f, "invalid SHA1 hash length, expected 20 bytes"),
+            Self::InvalidMultihash => write!(
+                f,
+                "invalid btmh multihash, expected a SHA-256 (0x12) entry of 32 bytes (0x20)"
+            ),
+            Self::MissingHash => write!(f, "magnet link doesn't carry a hash of this version"),
         }
     }
 }
 
 impl std::error::Error for HashBytesError {}
 
+/// Encodes a v2 info_hash as a BEP 9 multihash: the SHA-256 function code
+/// (`0x12`), the digest length (`0x20`), then the digest itself, hex-encoded.
+pub fn encode_v2_multihash(digest: &[u8; 32]) -> String {
+    let mut bytes = Vec::with_capacity(2 + digest.len());
+    bytes.push(0x12);
+    bytes.push(0x20);
+    bytes.extend_from_slice(digest);
+    hex::encode(bytes)
+}
+
 impl MagnetLink {
-    /// Converts the `info_hash` (a hexadecimal or Base32 string) to a 20-byte
-    /// SHA1 hash.
-    ///
-    /// This function will take the `info_hash` from the magnet link, which can
-    /// be provided in either hexadecimal or Base32 encoding, and convert it
-    /// to a fixed-length 20-byte array representing the SHA1 hash.
-    ///
-    /// # Returns
-    ///
-    /// Returns a `Result`:
-    /// - `Ok([u8; 20])`: A 20-byte array representing the decoded SHA1 hash of
-    ///   the torrent info hash.
-    /// - `Err(HashBytesError)`: An error if the `info_hash` is of an
-    ///   unsupported length, has invalid hexadecimal characters, or is
-    ///   improperly formatted in Base32.
+    /// The v1 (`urn:btih:`) hash string carried by this link, if any, exactly
+    /// as it appeared in the URI.
+    pub fn v1_hash(&self) -> Option<&str> {
+        self.hashes.iter().find_map(|hash| match hash {
+            InfoHash::V1(value) => Some(value.as_str()),
+            InfoHash::V2(_) => None,
+        })
+    }
+
+    /// The v2 (`urn:btmh:`) multihash string carried by this link, if any,
+    /// exactly as it appeared in the URI.
+    pub fn v2_hash(&self) -> Option<&str> {
+        self.hashes.iter().find_map(|hash| match hash {
+            InfoHash::V2(value) => Some(value.as_str()),
+            InfoHash::V1(_) => None,
+        })
+    }
+
+    /// Converts the v1 `info_hash` (a hexadecimal or Base32 string) to a
+    /// 20-byte SHA1 hash.
     ///
     /// # Errors
     ///
-    /// This function may return the following errors:
-    ///
-    /// - `HashBytesError::UnsupportedLength`: The `info_hash` is neither 32 nor
-    ///   40 characters in length.
-    /// - `HashBytesError::InvalidHex`: The `info_hash` contains invalid
-    ///   hexadecimal characters when the input is expected to be hexadecimal.
-    /// - `HashBytesError::InvalidBase32`: The `info_hash` is in an invalid
-    ///   Base32 format when the input is expected to be Base32.
-    /// - `HashBytesError::InvalidLength`: The decoded value is not 20 bytes in
-    ///   length, which is the expected size for a SHA1 hash.
+    /// - `HashBytesError::MissingHash`: this link carries no `urn:btih:` entry.
+    /// - `HashBytesError::UnsupportedLength`: the hash is neither 32 nor 40
+    ///   characters long.
+    /// - `HashBytesError::InvalidHex`: the hash contains invalid hexadecimal
+    ///   characters when a 40-character (hex) hash was expected.
+    /// - `HashBytesError::InvalidBase32`: the hash is invalid Base32 when a
+    ///   32-character (Base32) hash was expected.
+    /// - `HashBytesError::InvalidLength`: the decoded value isn't 20 bytes.
     ///
     /// # Example
     ///
     /// ```rust
-    /// use rustorrent_parser::magnet::MagnetLink;
+    /// use rustorrent_parser::magnet::{InfoHash, MagnetLink};
     ///
     /// let magnet_link = MagnetLink {
-    ///     info_hash: "d6a67b7e10b219d01f84c1c99962f060c18bb658".to_string(),
+    ///     hashes: vec![InfoHash::V1("d6a67b7e10b219d01f84c1c99962f060c18bb658".to_string())],
     ///     display_name: None,
     ///     trackers: Vec::new(),
     ///     web_seeds: Vec::new(),
@@ -160,7 +203,8 @@ impl MagnetLink {
     /// assert_eq!(hash.unwrap().len(), 20);
     /// ```
     pub fn hash_bytes(&self) -> Result<[u8; 20], HashBytesError> {
-        let cleaned = self.info_hash.to_ascii_lowercase();
+        let raw = self.v1_hash().ok_or(HashBytesError::MissingHash)?;
+        let cleaned = raw.to_ascii_lowercase();
 
         if cleaned.len() == 40 {
             let decoded = hex::decode(&cleaned).map_err(HashBytesError::InvalidHex)?;
@@ -177,6 +221,60 @@ impl MagnetLink {
             Err(HashBytesError::UnsupportedLength)
         }
     }
+
+    /// Decodes the v2 `urn:btmh:` multihash into its raw 32-byte SHA-256
+    /// digest.
+    ///
+    /// # Errors
+    ///
+    /// - `HashBytesError::MissingHash`: this link carries no `urn:btmh:` entry.
+    /// - `HashBytesError::InvalidHex`: the multihash isn't valid hex.
+    /// - `HashBytesError::InvalidMultihash`: the multihash isn't a SHA-256
+    ///   (`0x12`) entry of 32 bytes (`0x20`).
+    pub fn hash_bytes_v2(&self) -> Result<[u8; 32], HashBytesError> {
+        let raw = self.v2_hash().ok_or(HashBytesError::MissingHash)?;
+        let decoded = hex::decode(raw).map_err(HashBytesError::InvalidHex)?;
+
+        let [0x12, 0x20, digest @ ..] = decoded.as_slice() else {
+            return Err(HashBytesError::InvalidMultihash);
+        };
+        digest
+            .try_into()
+            .map_err(|_| HashBytesError::InvalidMultihash)
+    }
+
+    /// Serializes this magnet link back into its URI form
+    /// (`magnet:?xt=...&dn=...&tr=...`), percent-encoding every value.
+    pub fn to_uri(&self) -> String {
+        let mut url = url::Url::parse("magnet:?").expect("\"magnet:?\" is a valid URL");
+        {
+            let mut pairs = url.query_pairs_mut();
+            pairs.clear();
+            for hash in &self.hashes {
+                match hash {
+                    InfoHash::V1(value) => {
+                        pairs.append_pair("xt", &format!("urn:btih:{value}"));
+                    }
+                    InfoHash::V2(value) => {
+                        pairs.append_pair("xt", &format!("urn:btmh:{value}"));
+                    }
+                }
+            }
+            if let Some(name) = &self.display_name {
+                pairs.append_pair("dn", name);
+            }
+            for tracker in &self.trackers {
+                pairs.append_pair("tr", tracker);
+            }
+            for web_seed in &self.web_seeds {
+                pairs.append_pair("ws", web_seed);
+            }
+            for (key, value) in &self.params {
+                pairs.append_pair(key, value);
+            }
+        }
+        url.to_string()
+    }
 }
 
 #[cfg(test)]
@@ -187,10 +285,64 @@ mod tests {
     fn should_parse_academic_link() {
         let url = "magnet:?xt=urn:btih:d984f67af9917b214cd8b6048ab5624c7df6a07a&tr=https%3A%2F%2Facademictorrents.com%2Fannounce.php&tr=udp%3A%2F%2Ftracker.coppersurfer.tk%3A6969&tr=udp%3A%2F%2Ftracker.opentrackr.org%3A1337%2Fannounce";
         let magnet = MagnetLink::from_str(url).unwrap();
-        assert_eq!(magnet.info_hash, "d984f67af9917b214cd8b6048ab5624c7df6a07a");
+        assert_eq!(
+            magnet.v1_hash(),
+            Some("d984f67af9917b214cd8b6048ab5624c7df6a07a")
+        );
         assert_eq!(magnet.display_name, None);
         assert_eq!(magnet.trackers.len(), 3);
         assert!(magnet.web_seeds.is_empty());
         assert!(magnet.params.is_empty());
     }
+
+    #[test]
+    fn should_parse_hybrid_link_with_btih_and_btmh() {
+        let url = "magnet:?xt=urn:btih:d984f67af9917b214cd8b6048ab5624c7df6a07a&xt=urn:btmh:1220d6a67b7e10b219d01f84c1c99962f060c18bb6580000000000000000000000000000&dn=example";
+        let magnet = MagnetLink::from_str(url).unwrap();
+        assert_eq!(
+            magnet.v1_hash(),
+            Some("d984f67af9917b214cd8b6048ab5624c7df6a07a")
+        );
+        assert!(magnet.v2_hash().unwrap().starts_with("1220"));
+        assert_eq!(magnet.display_name.as_deref(), Some("example"));
+    }
+
+    #[test]
+    fn should_round_trip_to_uri() {
+        let magnet = MagnetLink {
+            hashes: vec![InfoHash::V1(
+                "d984f67af9917b214cd8b6048ab5624c7df6a07a".to_string(),
+            )],
+            display_name: Some("example torrent".to_string()),
+            trackers: vec!["https://example.com/announce".to_string()],
+            web_seeds: Vec::new(),
+            params: Vec::new(),
+        };
+
+        let uri = magnet.to_uri();
+        let parsed = MagnetLink::from_str(&uri).unwrap();
+        assert_eq!(parsed.v1_hash(), magnet.v1_hash());
+        assert_eq!(parsed.display_name, magnet.display_name);
+        assert_eq!(parsed.trackers, magnet.trackers);
+    }
+
+    #[test]
+    fn should_round_trip_hybrid_to_uri() {
+        let magnet = MagnetLink {
+            hashes: vec![
+                InfoHash::V1("d984f67af9917b214cd8b6048ab5624c7df6a07a".to_string()),
+                InfoHash::V2(encode_v2_multihash(&[0x42; 32])),
+            ],
+            display_name: Some("example torrent".to_string()),
+            trackers: Vec::new(),
+            web_seeds: Vec::new(),
+            params: Vec::new(),
+        };
+
+        let uri = magnet.to_uri();
+        let parsed = MagnetLink::from_str(&uri).unwrap();
+        assert_eq!(parsed.v1_hash(), magnet.v1_hash());
+        assert_eq!(parsed.v2_hash(), magnet.v2_hash());
+        assert_eq!(parsed.hash_bytes_v2().unwrap(), [0x42; 32]);
+    }
 }
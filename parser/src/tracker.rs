@@ -0,0 +1,338 @@
+//! An HTTP tracker client that turns a torrent's `announce`/`announce-list`
+//! into a list of peers (BEP 3), understanding both the compact (BEP 23) and
+//! dictionary peer formats.
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+/// Errors that can occur while announcing to a tracker.
+#[derive(Debug)]
+pub enum TrackerError {
+    /// The HTTP request itself failed (connection, timeout, bad status, ...).
+    Request(reqwest::Error),
+    /// The response body wasn't a well-formed bencoded announce response.
+    Decode(serde_bencode::Error),
+    /// The tracker responded successfully but reported a `failure reason`.
+    Failure(String),
+}
+
+impl From<reqwest::Error> for TrackerError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::Request(value)
+    }
+}
+
+impl From<serde_bencode::Error> for TrackerError {
+    fn from(value: serde_bencode::Error) -> Self {
+        Self::Decode(value)
+    }
+}
+
+impl std::fmt::Display for TrackerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Request(inner) => write!(f, "tracker request failed: {inner}"),
+            Self::Decode(inner) => write!(f, "invalid tracker response: {inner}"),
+            Self::Failure(reason) => write!(f, "tracker reported a failure: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for TrackerError {}
+
+/// Parameters sent to a tracker on every announce.
+#[derive(Debug, Clone)]
+pub struct AnnounceRequest {
+    /// The torrent's v1 info_hash, as returned by
+    /// [`crate::TorrentFile::info_hash_bytes`].
+    pub info_hash: [u8; 20],
+    /// This client's self-chosen 20-byte peer id.
+    pub peer_id: [u8; 20],
+    /// The port this client is listening for incoming peer connections on.
+    pub port: u16,
+    /// Bytes uploaded so far in this session.
+    pub uploaded: u64,
+    /// Bytes downloaded so far in this session.
+    pub downloaded: u64,
+    /// Bytes left to download to complete the torrent.
+    pub left: u64,
+}
+
+impl AnnounceRequest {
+    /// Builds an announce request for `file`, taking its v1 info_hash and
+    /// deriving `left` from the torrent's total content length and
+    /// `downloaded` so callers don't have to sum file lengths themselves.
+    pub fn for_torrent(
+        file: &crate::TorrentFile,
+        peer_id: [u8; 20],
+        port: u16,
+        uploaded: u64,
+        downloaded: u64,
+    ) -> Self {
+        let total_len: u64 = file.info.files().iter().map(|(_, length)| *length).sum();
+        Self {
+            info_hash: file.info_hash_bytes(),
+            peer_id,
+            port,
+            uploaded,
+            downloaded,
+            left: total_len.saturating_sub(downloaded),
+        }
+    }
+}
+
+/// A tracker's response to a successful announce.
+#[derive(Debug, Clone)]
+pub struct AnnounceResponse {
+    /// Seconds the client should wait before the next regular announce.
+    pub interval: u32,
+    /// Minimum seconds the client must wait before re-announcing, if given.
+    pub min_interval: Option<u32>,
+    /// The peers the tracker knows about for this torrent.
+    pub peers: Vec<SocketAddr>,
+}
+
+/// Raw, bencode-shaped announce response, before peers are normalized into
+/// [`SocketAddr`]s.
+#[derive(Debug, serde::Deserialize)]
+struct RawAnnounceResponse {
+    #[serde(default, rename = "failure reason")]
+    failure_reason: Option<String>,
+    #[serde(default)]
+    interval: u32,
+    #[serde(default, rename = "min interval")]
+    min_interval: Option<u32>,
+    #[serde(default)]
+    peers: RawPeers,
+}
+
+/// A tracker may return peers either compacted into one byte string (BEP 23)
+/// or as a list of `{ip, port}` dictionaries.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(untagged)]
+enum RawPeers {
+    #[default]
+    None,
+    Compact(#[serde(with = "serde_bytes")] serde_bytes::ByteBuf),
+    Dict(Vec<RawPeerEntry>),
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct RawPeerEntry {
+    ip: String,
+    port: u16,
+}
+
+impl RawPeers {
+    fn into_addrs(self) -> Vec<SocketAddr> {
+        match self {
+            Self::None => Vec::new(),
+            Self::Compact(bytes) => bytes
+                .chunks_exact(6)
+                .map(|chunk| {
+                    let ip = Ipv4Addr::new(chunk[0], chunk[1], chunk[2], chunk[3]);
+                    let port = u16::from_be_bytes([chunk[4], chunk[5]]);
+                    SocketAddr::new(IpAddr::V4(ip), port)
+                })
+                .collect(),
+            Self::Dict(entries) => entries
+                .into_iter()
+                .filter_map(|entry| {
+                    entry
+                        .ip
+                        .parse::<IpAddr>()
+                        .ok()
+                        .map(|ip| SocketAddr::new(ip, entry.port))
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Percent-encodes `bytes` as raw octets (not UTF-8 text), as required for
+/// the `info_hash` and `peer_id` query parameters (BEP 3): every byte outside
+/// the unreserved set is escaped, regardless of whether it forms valid UTF-8.
+fn percent_encode_bytes(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() * 3);
+    for &byte in bytes {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                out.push(byte as char);
+            }
+            _ => {
+                out.push('%');
+                out.push_str(&format!("{byte:02X}"));
+            }
+        }
+    }
+    out
+}
+
+fn build_url(tracker: &str, request: &AnnounceRequest) -> String {
+    let separator = if tracker.contains('?') { '&' } else { '?' };
+    format!(
+        "{tracker}{separator}info_hash={}&peer_id={}&port={}&uploaded={}&downloaded={}&left={}&compact=1",
+        percent_encode_bytes(&request.info_hash),
+        percent_encode_bytes(&request.peer_id),
+        request.port,
+        request.uploaded,
+        request.downloaded,
+        request.left,
+    )
+}
+
+/// Announces to a single tracker and returns its peer list.
+///
+/// `tracker` is the raw announce URL (HTTP or HTTPS), as found in
+/// `announce`/`announce-list`.
+pub async fn announce(
+    tracker: &str,
+    request: &AnnounceRequest,
+) -> Result<AnnounceResponse, TrackerError> {
+    let url = build_url(tracker, request);
+    let response = reqwest::get(url).await?.error_for_status()?;
+    let body = response.bytes().await?;
+    let raw: RawAnnounceResponse = serde_bencode::from_bytes(&body)?;
+
+    if let Some(reason) = raw.failure_reason {
+        return Err(TrackerError::Failure(reason));
+    }
+
+    Ok(AnnounceResponse {
+        interval: raw.interval,
+        min_interval: raw.min_interval,
+        peers: raw.peers.into_addrs(),
+    })
+}
+
+/// Announces to a torrent's tracker tiers (as laid out in `announce-list`),
+/// trying every tracker in order, tier by tier, and returning the first
+/// successful response.
+pub async fn announce_tiers(
+    tiers: &[Vec<String>],
+    request: &AnnounceRequest,
+) -> Result<AnnounceResponse, TrackerError> {
+    let mut last_err = None;
+    for tier in tiers {
+        for tracker in tier {
+            match announce(tracker, request).await {
+                Ok(response) => return Ok(response),
+                Err(err) => last_err = Some(err),
+            }
+        }
+    }
+    Err(last_err.unwrap_or_else(|| TrackerError::Failure("no trackers to announce to".into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request() -> AnnounceRequest {
+        AnnounceRequest {
+            info_hash: [0xAB; 20],
+            peer_id: *b"-RS0001-123456789012",
+            port: 6881,
+            uploaded: 1,
+            downloaded: 2,
+            left: 3,
+        }
+    }
+
+    #[test]
+    fn percent_encode_bytes_leaves_unreserved_bytes_untouched() {
+        assert_eq!(percent_encode_bytes(b"Az09-_.~"), "Az09-_.~");
+    }
+
+    #[test]
+    fn percent_encode_bytes_escapes_everything_else() {
+        assert_eq!(percent_encode_bytes(&[0xAB, 0x00, b' ']), "%AB%00%20");
+    }
+
+    #[test]
+    fn build_url_appends_a_query_string_with_a_question_mark() {
+        let url = build_url("https://tracker.example/announce", &request());
+        assert!(url.starts_with("https://tracker.example/announce?info_hash="));
+        assert!(url.contains("&peer_id=-RS0001-123456789012"));
+        assert!(url.contains("&port=6881"));
+        assert!(url.contains("&uploaded=1"));
+        assert!(url.contains("&downloaded=2"));
+        assert!(url.contains("&left=3"));
+        assert!(url.contains("&compact=1"));
+    }
+
+    #[test]
+    fn build_url_appends_params_with_an_ampersand_when_the_tracker_url_already_has_a_query() {
+        let url = build_url("https://tracker.example/announce?passkey=abc", &request());
+        assert!(url.starts_with("https://tracker.example/announce?passkey=abc&info_hash="));
+    }
+
+    #[test]
+    fn raw_peers_none_has_no_addrs() {
+        assert_eq!(RawPeers::None.into_addrs(), Vec::new());
+    }
+
+    #[test]
+    fn raw_peers_compact_decodes_ipv4_and_port() {
+        let bytes = serde_bytes::ByteBuf::from(vec![127, 0, 0, 1, 0x1A, 0xE1]);
+        let addrs = RawPeers::Compact(bytes).into_addrs();
+        assert_eq!(
+            addrs,
+            vec![SocketAddr::new(
+                IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
+                0x1AE1
+            )]
+        );
+    }
+
+    #[test]
+    fn for_torrent_derives_left_from_the_torrent_s_total_length() {
+        let dir = std::env::temp_dir().join("rustorrent-parser-tracker-test-for-torrent");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("file.txt"), b"hello world").unwrap();
+
+        let file = crate::builder::TorrentBuilder::new().build(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let request = AnnounceRequest::for_torrent(&file, [0x42; 20], 6881, 0, 4);
+        assert_eq!(request.info_hash, file.info_hash_bytes());
+        assert_eq!(request.peer_id, [0x42; 20]);
+        assert_eq!(request.port, 6881);
+        assert_eq!(request.uploaded, 0);
+        assert_eq!(request.downloaded, 4);
+        assert_eq!(request.left, 11 - 4);
+    }
+
+    #[test]
+    fn for_torrent_saturates_left_when_downloaded_overshoots_the_total() {
+        let dir = std::env::temp_dir().join("rustorrent-parser-tracker-test-overshoot");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("file.txt"), b"hi").unwrap();
+
+        let file = crate::builder::TorrentBuilder::new().build(&dir).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let request = AnnounceRequest::for_torrent(&file, [0; 20], 6881, 0, 1_000);
+        assert_eq!(request.left, 0);
+    }
+
+    #[test]
+    fn raw_peers_dict_decodes_valid_entries_and_skips_unparsable_ones() {
+        let addrs = RawPeers::Dict(vec![
+            RawPeerEntry {
+                ip: "10.0.0.1".into(),
+                port: 6881,
+            },
+            RawPeerEntry {
+                ip: "not-an-ip".into(),
+                port: 1,
+            },
+        ])
+        .into_addrs();
+        assert_eq!(
+            addrs,
+            vec![SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), 6881)]
+        );
+    }
+}
@@ -0,0 +1,265 @@
+//! Checks downloaded data against the piece hashes recorded in a torrent's
+//! `info` dictionary.
+
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use sha1::{Digest, Sha1};
+
+use crate::file::TorrentInfo;
+
+/// Outcome of verifying a single piece against its expected SHA1 hash.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceStatus {
+    /// The piece's hash matches the one recorded in `info.pieces`.
+    Ok,
+    /// The piece's hash does not match the one recorded in `info.pieces`.
+    Bad,
+    /// At least one file covering this piece could not be read.
+    Missing,
+}
+
+/// Verification outcome for a single file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileStatus {
+    /// The file exists, is the expected length, and every piece overlapping
+    /// it is [`PieceStatus::Ok`].
+    Ok,
+    /// The file exists and is the expected length, but at least one
+    /// overlapping piece is not [`PieceStatus::Ok`] (it is
+    /// [`PieceStatus::Bad`], or [`PieceStatus::Missing`] because another
+    /// file sharing it could not be read).
+    Bad,
+    /// The file could not be read at all.
+    Missing,
+    /// The file exists but its on-disk length doesn't match the length
+    /// recorded in the torrent's metadata.
+    Sized,
+}
+
+/// Verification outcome for a single file, derived from the status of every
+/// piece that overlaps its byte range.
+#[derive(Debug, Clone)]
+pub struct FileVerification {
+    /// Path of the file, relative to the root passed to [`TorrentInfo::verify`].
+    pub path: PathBuf,
+    /// Byte range this file occupies in the torrent's logical stream.
+    pub range: Range<u64>,
+    /// This file's verification outcome.
+    pub status: FileStatus,
+}
+
+/// Result of verifying on-disk data against a torrent's piece hashes.
+#[derive(Debug, Clone, Default)]
+pub struct VerificationReport {
+    /// Status of every piece, in piece order.
+    pub pieces: Vec<PieceStatus>,
+    /// Status of every file, in declared order.
+    pub files: Vec<FileVerification>,
+}
+
+impl VerificationReport {
+    /// `true` if every piece in the torrent matched its expected hash.
+    pub fn is_complete(&self) -> bool {
+        self.pieces.iter().all(|status| *status == PieceStatus::Ok)
+    }
+}
+
+/// A file's position within the torrent's logical (concatenated) byte stream.
+struct FileSpan {
+    path: PathBuf,
+    range: Range<u64>,
+}
+
+fn file_spans(info: &TorrentInfo) -> Vec<FileSpan> {
+    let mut offset = 0u64;
+    info.files()
+        .into_iter()
+        .map(|(path, length)| {
+            let start = offset;
+            offset += length;
+            FileSpan {
+                path,
+                range: start..offset,
+            }
+        })
+        .collect()
+}
+
+/// Indices of the pieces overlapping `span`, assuming pieces are
+/// `piece_length` bytes wide and laid out from offset `0`.
+fn overlapping_pieces(span: &FileSpan, piece_length: u64) -> Range<usize> {
+    if span.range.start == span.range.end {
+        return 0..0;
+    }
+    let first = (span.range.start / piece_length) as usize;
+    let last = ((span.range.end - 1) / piece_length) as usize;
+    first..(last + 1)
+}
+
+/// Reads the logical byte range `range`, which may straddle several files, by
+/// reading from each overlapping file under `root` in turn. Returns `None` if
+/// any overlapping file is missing or cannot be read in full.
+fn read_logical_range(root: &Path, spans: &[FileSpan], range: Range<u64>) -> Option<Vec<u8>> {
+    let mut buffer = Vec::with_capacity((range.end - range.start) as usize);
+    for span in spans {
+        if span.range.end <= range.start || span.range.start >= range.end {
+            continue;
+        }
+        let read_start = range.start.max(span.range.start) - span.range.start;
+        let read_end = range.end.min(span.range.end) - span.range.start;
+
+        let mut file = File::open(root.join(&span.path)).ok()?;
+        file.seek(SeekFrom::Start(read_start)).ok()?;
+        let mut chunk = vec![0u8; (read_end - read_start) as usize];
+        file.read_exact(&mut chunk).ok()?;
+        buffer.extend_from_slice(&chunk);
+    }
+    Some(buffer)
+}
+
+impl TorrentInfo {
+    /// Verifies the files under `root` against this torrent's v1 piece
+    /// hashes. Returns `None` for a v2-only torrent, which carries no
+    /// `pieces` to check against, or for a malformed torrent whose
+    /// `piece length` is `0` (which would otherwise divide by zero below).
+    ///
+    /// Files are treated as one contiguous logical stream, in the order
+    /// returned by [`TorrentInfo::files`], so pieces that straddle file
+    /// boundaries in multi-file mode are hashed correctly. The last piece is
+    /// expected to be shorter than `piece_length` if the total content size
+    /// isn't an exact multiple of it. A missing file marks every piece it
+    /// overlaps as [`PieceStatus::Missing`]; a file shorter than recorded
+    /// marks only the pieces reaching past its on-disk end as
+    /// [`PieceStatus::Missing`].
+    pub fn verify(&self, root: &Path) -> Option<VerificationReport> {
+        let piece_hashes = &self.as_v1()?.pieces;
+        let piece_length = self.piece_length();
+        if piece_length == 0 {
+            return None;
+        }
+        let spans = file_spans(self);
+        let total_len = spans.last().map(|span| span.range.end).unwrap_or(0);
+
+        let pieces: Vec<PieceStatus> = piece_hashes
+            .chunks_exact(20)
+            .enumerate()
+            .map(|(index, expected)| {
+                let start = index as u64 * piece_length;
+                let end = (start + piece_length).min(total_len);
+                match read_logical_range(root, &spans, start..end) {
+                    Some(data) => {
+                        let mut hasher = Sha1::new();
+                        hasher.update(&data);
+                        if hasher.finalize().as_slice() == expected {
+                            PieceStatus::Ok
+                        } else {
+                            PieceStatus::Bad
+                        }
+                    }
+                    None => PieceStatus::Missing,
+                }
+            })
+            .collect();
+
+        let files = spans
+            .into_iter()
+            .map(|span| {
+                let status = match std::fs::metadata(root.join(&span.path)) {
+                    Err(_) => FileStatus::Missing,
+                    Ok(metadata) if metadata.len() != span.range.end - span.range.start => {
+                        FileStatus::Sized
+                    }
+                    Ok(_) => {
+                        let covering = overlapping_pieces(&span, piece_length);
+                        if covering
+                            .clone()
+                            .all(|index| matches!(pieces.get(index), Some(PieceStatus::Ok)))
+                        {
+                            FileStatus::Ok
+                        } else {
+                            FileStatus::Bad
+                        }
+                    }
+                };
+                FileVerification {
+                    path: span.path,
+                    range: span.range,
+                    status,
+                }
+            })
+            .collect();
+
+        Some(VerificationReport { pieces, files })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(start: u64, end: u64) -> FileSpan {
+        FileSpan {
+            path: PathBuf::from("irrelevant"),
+            range: start..end,
+        }
+    }
+
+    #[test]
+    fn overlapping_pieces_is_empty_for_an_empty_file() {
+        assert_eq!(overlapping_pieces(&span(10, 10), 4), 0..0);
+    }
+
+    #[test]
+    fn overlapping_pieces_covers_a_span_straddling_piece_boundaries() {
+        // Bytes 0..10 touch pieces [0,4), [4,8), and [8,12).
+        assert_eq!(overlapping_pieces(&span(0, 10), 4), 0..3);
+    }
+
+    #[test]
+    fn overlapping_pieces_covers_a_span_aligned_to_one_piece() {
+        assert_eq!(overlapping_pieces(&span(4, 8), 4), 1..2);
+    }
+
+    #[test]
+    fn read_logical_range_concatenates_across_file_boundaries() {
+        let dir = std::env::temp_dir().join("rustorrent-parser-verify-test-read-range");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("a"), b"hello").unwrap();
+        std::fs::write(dir.join("b"), b"world").unwrap();
+
+        let spans = vec![
+            FileSpan {
+                path: PathBuf::from("a"),
+                range: 0..5,
+            },
+            FileSpan {
+                path: PathBuf::from("b"),
+                range: 5..10,
+            },
+        ];
+        let data = read_logical_range(&dir, &spans, 2..8);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(data.as_deref(), Some(&b"llowor"[..]));
+    }
+
+    #[test]
+    fn read_logical_range_returns_none_when_a_file_is_missing() {
+        let dir = std::env::temp_dir().join("rustorrent-parser-verify-test-missing-file");
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let spans = vec![FileSpan {
+            path: PathBuf::from("missing"),
+            range: 0..5,
+        }];
+        let data = read_logical_range(&dir, &spans, 0..5);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(data, None);
+    }
+}